@@ -4,7 +4,12 @@ use crate::ametheed::assets::loader::Loader;
 use specs::prelude::*;
 use crate::ametheed::assets::FormatValue;
 use crate::ametheed::error::Error;
-use std::{sync::Arc, time::Instant};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+    time::Instant,
+};
 
 use derive_new::new;
 
@@ -39,6 +44,27 @@ impl<D: 'static> Clone for Box<dyn Reload<D>> {
     }
 }
 
+/// A type that knows which filesystem path(s) back the asset it loaded.
+/// Implemented by asset sources so the `Watch` hot-reload strategy knows
+/// which changed paths are worth caring about.
+pub trait Source: Send + Sync + 'static {
+    /// Every path this asset was loaded from, if any.
+    fn paths(&self) -> Vec<PathBuf>;
+}
+
+/// The kind of filesystem change a `Watch` strategy observed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileEventKind {
+    Created,
+    Modified,
+}
+
+/// A single filesystem change forwarded from the watcher thread.
+#[derive(Debug, Clone)]
+pub struct FileEvent {
+    pub path: PathBuf,
+    pub kind: FileEventKind,
+}
 
 /// An ECS resource which allows to configure hot reloading.
 ///
@@ -55,6 +81,7 @@ impl<D: 'static> Clone for Box<dyn Reload<D>> {
 #[derive(Clone, Debug)]
 pub struct HotReloadStrategy {
     inner: HotReloadStrategyInner,
+    generation: u64,
 }
 
 impl HotReloadStrategy {
@@ -68,6 +95,7 @@ impl HotReloadStrategy {
                 last: Instant::now(),
                 frame_number: MAX,
             },
+            generation: 0,
         }
     }
 
@@ -80,6 +108,7 @@ impl HotReloadStrategy {
                 triggered: false,
                 frame_number: MAX,
             },
+            generation: 0,
         }
     }
 
@@ -87,6 +116,38 @@ impl HotReloadStrategy {
     pub fn never() -> Self {
         HotReloadStrategy {
             inner: HotReloadStrategyInner::Never,
+            generation: 0,
+        }
+    }
+
+    /// Watches `dir` on a background thread and reloads only the assets
+    /// whose backing path actually changed, instead of polling on a timer.
+    pub fn watch<P: AsRef<Path>>(dir: P) -> Self {
+        use std::u64::MAX;
+
+        let (tx, rx) = mpsc::channel();
+        let dir = dir.as_ref().to_path_buf();
+
+        std::thread::Builder::new()
+            .name("hot-reload-watcher".to_string())
+            .spawn(move || watch_thread(dir, tx))
+            .expect("failed to spawn hot-reload watcher thread");
+
+        HotReloadStrategy {
+            inner: HotReloadStrategyInner::Watch {
+                frame_number: MAX,
+                changed: Arc::new(Mutex::new(rx)),
+                tracked: HashSet::new(),
+            },
+            generation: 0,
+        }
+    }
+
+    /// Registers the backing path(s) of a loaded asset so the `Watch`
+    /// strategy knows to act on changes to them. No-op for other strategies.
+    pub fn track<S: Source + ?Sized>(&mut self, source: &S) {
+        if let HotReloadStrategyInner::Watch { ref mut tracked, .. } = self.inner {
+            tracked.extend(source.paths());
         }
     }
 
@@ -108,9 +169,63 @@ impl HotReloadStrategy {
         match self.inner {
             HotReloadStrategyInner::Every { frame_number, .. } => frame_number == current_frame,
             HotReloadStrategyInner::Trigger { frame_number, .. } => frame_number == current_frame,
+            HotReloadStrategyInner::Watch { frame_number, .. } => frame_number == current_frame,
             HotReloadStrategyInner::Never => false,
         }
     }
+
+    /// The number of reload frames scheduled so far. Bumped once per frame
+    /// that actually triggers a reload, so consumers can cheaply tell
+    /// whether anything may have reloaded since they last checked, without
+    /// re-scanning any storages.
+    pub fn version(&self) -> u64 {
+        self.generation
+    }
+}
+
+/// Blocks on filesystem events under `dir` and forwards the ones we care
+/// about (create/modify) to the strategy over `tx`. Returns early if the
+/// watcher can't be started; errors on individual events are logged and
+/// skipped. Otherwise runs until the receiving end is dropped.
+fn watch_thread(dir: PathBuf, tx: mpsc::Sender<FileEvent>) {
+    use notify::{RecursiveMode, Watcher};
+
+    let (watch_tx, watch_rx) = mpsc::channel();
+    let mut watcher = match notify::recommended_watcher(watch_tx) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            log::error!(
+                "failed to start filesystem watcher for `{}`: {}",
+                dir.display(),
+                e
+            );
+            return;
+        }
+    };
+    if let Err(e) = watcher.watch(&dir, RecursiveMode::Recursive) {
+        log::error!("failed to watch `{}`: {}", dir.display(), e);
+        return;
+    }
+
+    for res in watch_rx {
+        let event = match res {
+            Ok(event) => event,
+            Err(e) => {
+                log::error!("filesystem watch error: {}", e);
+                continue;
+            }
+        };
+        let kind = match event.kind {
+            notify::EventKind::Create(_) => FileEventKind::Created,
+            notify::EventKind::Modify(_) => FileEventKind::Modified,
+            _ => continue,
+        };
+        for path in event.paths {
+            if tx.send(FileEvent { path, kind }).is_err() {
+                return;
+            }
+        }
+    }
 }
 
 impl Default for HotReloadStrategy {
@@ -119,7 +234,7 @@ impl Default for HotReloadStrategy {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 enum HotReloadStrategyInner {
     Every {
         interval: u8,
@@ -130,9 +245,49 @@ enum HotReloadStrategyInner {
         triggered: bool,
         frame_number: u64,
     },
+    Watch {
+        frame_number: u64,
+        changed: Arc<Mutex<mpsc::Receiver<FileEvent>>>,
+        tracked: HashSet<PathBuf>,
+    },
     Never,
 }
 
+impl std::fmt::Debug for HotReloadStrategyInner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HotReloadStrategyInner::Every {
+                interval,
+                last,
+                frame_number,
+            } => f
+                .debug_struct("Every")
+                .field("interval", interval)
+                .field("last", last)
+                .field("frame_number", frame_number)
+                .finish(),
+            HotReloadStrategyInner::Trigger {
+                triggered,
+                frame_number,
+            } => f
+                .debug_struct("Trigger")
+                .field("triggered", triggered)
+                .field("frame_number", frame_number)
+                .finish(),
+            HotReloadStrategyInner::Watch {
+                frame_number,
+                tracked,
+                ..
+            } => f
+                .debug_struct("Watch")
+                .field("frame_number", frame_number)
+                .field("tracked", tracked)
+                .finish(),
+            HotReloadStrategyInner::Never => f.write_str("Never"),
+        }
+    }
+}
+
 /// Builds a `HotReloadSystem`.
 #[derive(Debug, new)]
 pub struct HotReloadSystemDesc {
@@ -162,6 +317,8 @@ impl<'a> System<'a> for HotReloadSystem {
         #[cfg(feature = "profiler")]
         profile_scope!("hot_reload_system");
 
+        let mut scheduled = false;
+
         match strategy.inner {
             HotReloadStrategyInner::Trigger {
                 ref mut triggered,
@@ -169,6 +326,7 @@ impl<'a> System<'a> for HotReloadSystem {
             } => {
                 if *triggered {
                     *frame_number = time.frame_number() + 1;
+                    scheduled = true;
                 }
                 *triggered = false;
             }
@@ -180,9 +338,27 @@ impl<'a> System<'a> for HotReloadSystem {
                 if last.elapsed().as_secs() > u64::from(interval) {
                     *frame_number = time.frame_number() + 1;
                     *last = Instant::now();
+                    scheduled = true;
+                }
+            }
+            HotReloadStrategyInner::Watch {
+                ref mut frame_number,
+                ref changed,
+                ref tracked,
+            } => {
+                let changed = changed.lock().expect("hot-reload watch channel poisoned");
+                for event in changed.try_iter() {
+                    if tracked.contains(&event.path) {
+                        *frame_number = time.frame_number() + 1;
+                        scheduled = true;
+                    }
                 }
             }
             HotReloadStrategyInner::Never => {}
         }
+
+        if scheduled {
+            strategy.generation += 1;
+        }
     }
 }
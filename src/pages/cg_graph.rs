@@ -1,4 +1,5 @@
 pub mod ecs;
+pub mod layout;
 use crate::ametheed::ui::button::UiButtonBuilder;
 use crate::ametheed::ui::button::builder::UiButtonBuilderResources;
 use crate::ametheed::UiButton;
@@ -6,62 +7,127 @@ use crate::ametheed::ui::layout::Anchor;
 use crate::pages::cg_graph::ecs::components::Position;
 use crate::pages::cg_graph::ecs::components::Renderable;
 use crate::pages::cg_graph::ecs::components::Color;
+use crate::pages::cg_graph::ecs::resources::DrawCmd;
+use crate::pages::cg_graph::layout::Layout;
 use petgraph::dot::{Config, Dot};
 use petgraph::prelude::*;
 use seed::{prelude::*, *};
 use shared::learning_trajectory;
 use specs::prelude::*;
 use std::collections::HashMap;
-use web_sys::{HtmlCanvasElement};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
 
 const WIDTH: usize = 900;
 const HEIGHT: usize = 600;
-const RAD: u32 = 50;
+pub(crate) const RAD: u32 = 50;
+
+/// Whether anything in `current` differs from `prev`, including geometry,
+/// so an unchanged count doesn't mask a moved or recolored circle. This is
+/// a frame-level skip-or-redraw-everything check, not per-circle diffing:
+/// any change repaints the whole queue rather than only the changed entry.
+fn should_redraw(prev: &[DrawCmd], current: &[DrawCmd]) -> bool {
+    prev != current
+}
+
+#[cfg(test)]
+mod draw_diff_tests {
+    use super::*;
+
+    fn circle(x: f32) -> DrawCmd {
+        DrawCmd::Circle { x, y: 0.0, r: RAD as f32, color: Color::default() }
+    }
+
+    #[test]
+    fn unchanged_queue_skips_redraw() {
+        let prev = vec![DrawCmd::Clear, circle(10.0)];
+        let current = prev.clone();
+        assert!(!should_redraw(&prev, &current));
+    }
+
+    #[test]
+    fn moved_circle_with_same_count_forces_redraw() {
+        let prev = vec![DrawCmd::Clear, circle(10.0)];
+        let current = vec![DrawCmd::Clear, circle(20.0)];
+        assert!(should_redraw(&prev, &current));
+    }
+}
 
 #[derive(Debug)]
 pub struct Model {
-    pub pet: DiGraph<UiButton, f32>,
+    pub pet: DiGraph<CGNode, f32>,
     fill_color: Color,
     canvas: ElRef<HtmlCanvasElement>,
     pub specs: ecs::State,
     tics: usize,
+    /// Last frame's flushed draw commands, compared against the new queue
+    /// each frame to decide whether a repaint is needed.
+    prev_draw: Vec<DrawCmd>,
+    use_grid_layout: bool,
 }
 
 impl Model {
     fn render(&mut self) {
-        let rends = self.specs.inner.read_storage::<Renderable>();
-        let positions = self.specs.inner.read_storage::<Position>();
-        let cols = self.specs.inner.read_storage::<ecs::components::Color>();
-        for (_rend, pos) in (&rends, &positions).join() {}
-        let canvas = self.canvas.get().expect("get canvas element");
-        let ctx = seed::canvas_context_2d(&canvas);
-        for (_rend, pos, col) in (&rends, &positions, &cols).join() {
-            ctx.begin_path();
-            let x = pos.x;
-            let y = pos.y;
-            ctx.set_fill_style(&JsValue::from_str(&col.html_str()));
-            ctx.arc(
-                x as f64,
-                y as f64,
-                RAD.into(),
-                0.0,
-                std::f64::consts::PI * 2.,
-            );
-            ctx.fill();
+        self.specs.run_frame();
+        let queue = self
+            .specs
+            .inner
+            .read_resource::<ecs::resources::DrawQueue>()
+            .0
+            .clone();
+
+        if should_redraw(&self.prev_draw, &queue) {
+            let canvas = self.canvas.get().expect("get canvas element");
+            let ctx = seed::canvas_context_2d(&canvas);
+            for cmd in &queue {
+                Self::paint(&ctx, cmd);
+            }
         }
+        self.prev_draw = queue;
     }
 
-    fn detect_hover(&mut self, mouse_pos: (f32, f32)) {
-        let positions = self.specs.inner.read_storage::<Position>();
-        let rends = self.specs.inner.read_storage::<Renderable>();
-        let mut cols = self.specs.inner.write_storage::<ecs::components::Color>();
-        for (_rend, pos, mut col) in (&rends, &positions, &mut cols).join() {
-            if (mouse_pos.0 - pos.x) * (mouse_pos.0 - pos.x) + (mouse_pos.1 - pos.y) * (mouse_pos.1 - pos.y) < (RAD * RAD) as f32
-            {
-                col.b = 0;
+    fn paint(ctx: &CanvasRenderingContext2d, cmd: &DrawCmd) {
+        match cmd {
+            DrawCmd::Clear => ctx.clear_rect(0., 0., WIDTH as f64, HEIGHT as f64),
+            DrawCmd::Circle { x, y, r, color } => {
+                ctx.begin_path();
+                ctx.set_fill_style(&JsValue::from_str(&color.html_str()));
+                ctx.arc(*x as f64, *y as f64, *r as f64, 0.0, std::f64::consts::PI * 2.);
+                ctx.fill();
+            }
+            DrawCmd::Text { x, y, text } => {
+                ctx.set_fill_style(&JsValue::from_str("#000"));
+                ctx.fill_text(text, *x as f64, *y as f64);
             }
         }
-        // log!(mouse_pos);
+    }
+
+    fn detect_hover(&mut self, mouse_pos: (f32, f32)) {
+        self.specs
+            .inner
+            .write_resource::<ecs::resources::MousePosition>()
+            .0 = Some(mouse_pos);
+        self.specs.run_frame();
+    }
+
+    fn clear_hover(&mut self) {
+        self.specs
+            .inner
+            .write_resource::<ecs::resources::MousePosition>()
+            .0 = None;
+        self.specs.run_frame();
+    }
+
+    /// Renderable entities as `(entity id, center, label)`, for the
+    /// accessible overlay buttons drawn on top of the canvas.
+    fn accessible_nodes(&self) -> Vec<(u32, (f32, f32), String)> {
+        let entities = self.specs.inner.entities();
+        let rends = self.specs.inner.read_storage::<Renderable>();
+        let positions = self.specs.inner.read_storage::<Position>();
+        let labels = self.specs.inner.read_storage::<ecs::components::Label>();
+        (&entities, &rends, &positions, &labels)
+            .join()
+            .map(|(e, _, pos, label)| (e.id(), (pos.x, pos.y), label.0.clone()))
+            .collect()
     }
 }
 
@@ -80,6 +146,8 @@ impl Default for Model {
             fill_color: Color { r: 0, g: 255, b: 0 },
             canvas: Default::default(),
             specs,
+            prev_draw: Vec::new(),
+            use_grid_layout: false,
         }
     }
 }
@@ -111,9 +179,16 @@ pub enum Message {
     CGGraph(fetch::Result<learning_trajectory::CGGraph>),
     OnTick(RenderInfo),
     CanvasMouse(web_sys::MouseEvent),
+    CanvasLeave,
     DotFile,
     Rendered,
     ChangeColor,
+    ToggleLayout,
+    /// Keyboard focus moved to the accessible overlay button for this entity.
+    /// The overlay is plain DOM buttons; no AccessKit tree is built.
+    AccessFocus(u32),
+    /// The accessible overlay button for this entity was activated.
+    AccessActivate(u32),
 }
 
 #[derive(Debug)]
@@ -155,6 +230,7 @@ pub fn update(msg: Message, mdl: &mut Model, orders: &mut impl Orders<Message>)
             orders.after_next_render(Message::OnTick);
         }
         Message::ChangeColor => std::mem::swap(&mut mdl.fill_color.b, &mut mdl.fill_color.g),
+        Message::ToggleLayout => mdl.use_grid_layout = !mdl.use_grid_layout,
         // Message::Rendered => {
         //     draw(&mdl);
         //     // We want to call `.skip` to prevent infinite loop.
@@ -165,7 +241,7 @@ pub fn update(msg: Message, mdl: &mut Model, orders: &mut impl Orders<Message>)
             orders.perform_cmd(async { CGGraph(fetch_cg_graph().await) });
         }
         CGGraph(Ok(res)) => {
-            let mut gr = DiGraph::<UiButton, f32>::new();
+            let mut gr = DiGraph::<CGNode, f32>::new();
             let mut idx_map: HashMap<usize, NodeIndex> = HashMap::with_capacity(res.0.len());
             let but_b_res = UiButtonBuilderResource {
                 id: 0,
@@ -196,7 +272,7 @@ pub fn update(msg: Message, mdl: &mut Model, orders: &mut impl Orders<Message>)
                     .with_align(Anchor::MiddleLeft)
                     .build();
 
-                let g_node =  {
+                let g_node = CGNode {
                     color: Color {
                         r: 255 / (i as u8 + 1),
                         g: 255 - (255 / (i as u8 + 1)),
@@ -218,20 +294,29 @@ pub fn update(msg: Message, mdl: &mut Model, orders: &mut impl Orders<Message>)
                 );
             }
             mdl.pet = gr;
-            for node in mdl.pet.raw_nodes() {
-                let node = &node.weight;
+            let positions: HashMap<NodeIndex, (f32, f32)> = if mdl.use_grid_layout {
+                layout::GridLayout { cell: RAD as f32 }
+                    .position(&mdl.pet, (WIDTH as f32, HEIGHT as f32))
+            } else {
+                layout::ForceDirectedLayout::default()
+                    .position(&mdl.pet, (WIDTH as f32, HEIGHT as f32))
+            };
+            for idx in mdl.pet.node_indices() {
+                let node = &mdl.pet[idx];
+                let (x, y) = positions
+                    .get(&idx)
+                    .copied()
+                    .unwrap_or((node.pos_x as f32, node.pos_y as f32));
                 mdl.specs
                     .inner
                     .create_entity()
-                    .with(Position {
-                        x: node.pos_x as f32,
-                        y: node.pos_y as f32,
-                    })
+                    .with(Position { x, y })
                     .with(ecs::components::Color {
                         r: node.color.r,
                         g: node.color.g,
                         b: node.color.b,
                     })
+                    .with(ecs::components::Label(format!("id: {}", node.cg.id)))
                     .with(Renderable)
                     .build();
             }
@@ -251,6 +336,19 @@ pub fn update(msg: Message, mdl: &mut Model, orders: &mut impl Orders<Message>)
             let y = canv_pos.1;
             mdl.detect_hover((x as f32, y as f32));
         }
+        CanvasLeave => mdl.clear_hover(),
+        AccessFocus(id) => {
+            let entity = mdl.specs.inner.entities().entity(id);
+            mdl.specs
+                .inner
+                .write_resource::<ecs::resources::KeyboardFocus>()
+                .0 = Some(entity);
+            mdl.specs.run_frame();
+        }
+        AccessActivate(id) => {
+            let entity = mdl.specs.inner.entities().entity(id);
+            log!("accessibility activation", entity);
+        }
         // Task(Ok((id, res))) => {
         //     mdl.subjects.get_mut(&id).unwrap().learning_objectives = res;
         // }
@@ -269,32 +367,59 @@ async fn fetch_cg_graph() -> fetch::Result<learning_trajectory::CGGraph> {
     Ok(result)
 }
 
+/// A screen-reader-visible button placed over a node's hit area, so
+/// assistive tech gets real keyboard focus and activation instead of
+/// only the canvas pixels.
+fn access_overlay_button(id: u32, center: (f32, f32), label: String) -> Node<Message> {
+    button![
+        label,
+        attrs![At::TabIndex => 0],
+        style![
+            St::Position => "absolute",
+            St::Left => px(center.0 as i32 - RAD as i32),
+            St::Top => px(center.1 as i32 - RAD as i32),
+            St::Width => px(RAD * 2),
+            St::Height => px(RAD * 2),
+            St::Opacity => "0",
+        ],
+        ev(Ev::Focus, move |_| Message::AccessFocus(id)),
+        ev(Ev::Click, move |_| Message::AccessActivate(id)),
+    ]
+}
+
 pub fn view(model: &Model) -> Node<Message> {
     ul![
         li![button![
             "get cg_graph",
             ev(Ev::Click, |_| Message::FetchCGGraph)
         ]],
-        canvas![
-            el_ref(&model.canvas),
-            attrs![
-                At::Width => px(WIDTH),
-                At::Height => px(HEIGHT),
-            ],
-            style![
-                St::Border => "1px solid black",
+        div![
+            style![St::Position => "relative"],
+            canvas![
+                el_ref(&model.canvas),
+                attrs![
+                    At::Width => px(WIDTH),
+                    At::Height => px(HEIGHT),
+                ],
+                style![
+                    St::Border => "1px solid black",
+                ],
+                mouse_ev(Ev::MouseEnter, |mouse_event| Message::CanvasMouse(
+                    mouse_event
+                )),
+                mouse_ev(Ev::MouseLeave, |_| Message::CanvasLeave),
+                mouse_ev(Ev::MouseMove, |mouse_event| Message::CanvasMouse(
+                    mouse_event.unchecked_into()
+                ))
             ],
-            mouse_ev(Ev::MouseEnter, |mouse_event| Message::CanvasMouse(
-                mouse_event
-            )),
-            mouse_ev(Ev::MouseLeave, |mouse_event| Message::CanvasMouse(
-                mouse_event
-            )),
-            mouse_ev(Ev::MouseMove, |mouse_event| Message::CanvasMouse(
-                mouse_event.unchecked_into()
-            ))
+            model
+                .accessible_nodes()
+                .into_iter()
+                .map(|(id, center, label)| access_overlay_button(id, center, label))
+                .collect::<Vec<_>>(),
         ],
         button!["Change color", ev(Ev::Click, |_| Message::ChangeColor)],
+        button!["Toggle layout", ev(Ev::Click, |_| Message::ToggleLayout)],
         button!["get .dot file", ev(Ev::Click, |_| Message::DotFile)],
         li![format!("{:?}", model)]
     ]
@@ -0,0 +1,48 @@
+use specs::{Component, DenseVecStorage, NullStorage};
+
+/// World-space location of a node, in canvas pixel coordinates.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Position {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Component for Position {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Marker for entities painted as a circle on the canvas and considered
+/// for hit testing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Renderable;
+
+impl Component for Renderable {
+    type Storage = NullStorage<Self>;
+}
+
+/// RGB fill color, rendered via `html_str` as the canvas fill style.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub fn html_str(&self) -> String {
+        format!("#{:0>2x}{:0>2x}{:0>2x}", self.r, self.g, self.b)
+    }
+}
+
+impl Component for Color {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Human-readable label for a node, surfaced to assistive tech via the
+/// accessible overlay buttons.
+#[derive(Debug, Clone, Default)]
+pub struct Label(pub String);
+
+impl Component for Label {
+    type Storage = DenseVecStorage<Self>;
+}
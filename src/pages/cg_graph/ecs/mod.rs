@@ -0,0 +1,58 @@
+pub mod components;
+pub mod resources;
+pub mod systems;
+
+use specs::prelude::*;
+
+use self::components::{Color, Label, Position, Renderable};
+use self::resources::{DrawQueue, Hitboxes, Hovered, KeyboardFocus, MousePosition};
+use self::systems::{AfterLayoutSystem, DrawCmdSystem, HitTestSystem};
+
+/// The `specs` world backing the `cg_graph` page, plus the dispatcher that
+/// drives the per-frame hover pipeline (hitbox registration, hit test, and
+/// draw-command buffer).
+pub struct State {
+    pub inner: World,
+    dispatcher: Dispatcher<'static, 'static>,
+}
+
+impl std::fmt::Debug for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("State").finish()
+    }
+}
+
+impl State {
+    /// Builds a fresh world with all `cg_graph` components registered and
+    /// the hover dispatcher wired up.
+    pub fn init() -> Self {
+        let mut world = World::new();
+        world.register::<Position>();
+        world.register::<Renderable>();
+        world.register::<Color>();
+        world.register::<Label>();
+        world.insert(Hitboxes::default());
+        world.insert(Hovered::default());
+        world.insert(KeyboardFocus::default());
+        world.insert(MousePosition::default());
+        world.insert(DrawQueue::default());
+
+        let dispatcher = DispatcherBuilder::new()
+            .with(AfterLayoutSystem, "after_layout", &[])
+            .with(HitTestSystem, "hit_test", &["after_layout"])
+            .with(DrawCmdSystem, "draw_cmd", &["hit_test"])
+            .build();
+
+        State {
+            inner: world,
+            dispatcher,
+        }
+    }
+
+    /// Re-registers hitboxes, recomputes which entity (if any) is hovered,
+    /// and rebuilds this frame's draw-command buffer.
+    pub fn run_frame(&mut self) {
+        self.dispatcher.dispatch(&self.inner);
+        self.inner.maintain();
+    }
+}
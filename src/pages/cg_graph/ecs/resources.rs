@@ -0,0 +1,51 @@
+use specs::Entity;
+
+use super::components::Color;
+
+/// A circular hit region for one entity.
+#[derive(Debug, Clone, Copy)]
+pub struct Hitbox {
+    pub center: (f32, f32),
+    pub radius: f32,
+}
+
+impl Hitbox {
+    pub fn contains(&self, point: (f32, f32)) -> bool {
+        let dx = point.0 - self.center.0;
+        let dy = point.1 - self.center.1;
+        dx * dx + dy * dy < self.radius * self.radius
+    }
+}
+
+/// This frame's hitboxes, rebuilt every frame in paint order so later
+/// entries (drawn on top) win hit tests over earlier ones.
+#[derive(Debug, Default)]
+pub struct Hitboxes(pub Vec<(Entity, Hitbox)>);
+
+/// The topmost hitbox under the cursor, or `None` if nothing is hovered.
+#[derive(Debug, Default)]
+pub struct Hovered(pub Option<Entity>);
+
+/// The entity that holds keyboard focus via the accessible overlay, or
+/// `None`. Kept separate from `Hovered` so mouse movement can't clobber it.
+#[derive(Debug, Default)]
+pub struct KeyboardFocus(pub Option<Entity>);
+
+/// Cursor position in canvas-local pixel space. `None` when the cursor is
+/// off-canvas, e.g. after a `MouseLeave`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MousePosition(pub Option<(f32, f32)>);
+
+/// A single canvas draw instruction, compared frame-to-frame to decide
+/// whether a repaint is needed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawCmd {
+    Clear,
+    Circle { x: f32, y: f32, r: f32, color: Color },
+    Text { x: f32, y: f32, text: String },
+}
+
+/// This frame's draw commands, in paint order. They are flushed to the
+/// canvas only when they differ from the previous frame's.
+#[derive(Debug, Default)]
+pub struct DrawQueue(pub Vec<DrawCmd>);
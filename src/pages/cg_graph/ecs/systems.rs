@@ -0,0 +1,136 @@
+use specs::prelude::*;
+
+use super::components::{Color, Position, Renderable};
+use super::resources::{DrawCmd, DrawQueue, Hitbox, Hitboxes, Hovered, KeyboardFocus, MousePosition};
+use crate::pages::cg_graph::RAD;
+
+/// Registers a hitbox per renderable entity, in paint order, so
+/// `HitTestSystem` has up-to-date geometry to test the cursor against.
+pub struct AfterLayoutSystem;
+
+impl<'a> System<'a> for AfterLayoutSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Renderable>,
+        ReadStorage<'a, Position>,
+        Write<'a, Hitboxes>,
+    );
+
+    fn run(&mut self, (entities, rends, positions, mut hitboxes): Self::SystemData) {
+        hitboxes.0.clear();
+        for (entity, _rend, pos) in (&entities, &rends, &positions).join() {
+            hitboxes.0.push((
+                entity,
+                Hitbox {
+                    center: (pos.x, pos.y),
+                    radius: RAD as f32,
+                },
+            ));
+        }
+    }
+}
+
+/// Picks the topmost (last-registered) hitbox containing `cursor`, if any.
+fn topmost_hit(hitboxes: &[(Entity, Hitbox)], cursor: (f32, f32)) -> Option<Entity> {
+    hitboxes
+        .iter()
+        .rev()
+        .find(|(_, hitbox)| hitbox.contains(cursor))
+        .map(|(entity, _)| *entity)
+}
+
+/// Recomputes `Hovered` from scratch every frame, from the current
+/// `MousePosition` and `Hitboxes`.
+pub struct HitTestSystem;
+
+impl<'a> System<'a> for HitTestSystem {
+    type SystemData = (
+        Read<'a, Hitboxes>,
+        Read<'a, MousePosition>,
+        Write<'a, Hovered>,
+    );
+
+    fn run(&mut self, (hitboxes, mouse_pos, mut hovered): Self::SystemData) {
+        hovered.0 = mouse_pos.0.and_then(|pos| topmost_hit(&hitboxes.0, pos));
+    }
+}
+
+#[cfg(test)]
+mod hover_tests {
+    use super::*;
+
+    #[test]
+    fn topmost_wins_on_overlap() {
+        let mut world = World::new();
+        let bottom = world.create_entity().build();
+        let top = world.create_entity().build();
+        let hitboxes = vec![
+            (
+                bottom,
+                Hitbox {
+                    center: (0.0, 0.0),
+                    radius: 10.0,
+                },
+            ),
+            (
+                top,
+                Hitbox {
+                    center: (0.0, 0.0),
+                    radius: 10.0,
+                },
+            ),
+        ];
+
+        assert_eq!(topmost_hit(&hitboxes, (0.0, 0.0)), Some(top));
+    }
+
+    #[test]
+    fn no_hit_outside_any_hitbox() {
+        let mut world = World::new();
+        let only = world.create_entity().build();
+        let hitboxes = vec![(
+            only,
+            Hitbox {
+                center: (0.0, 0.0),
+                radius: 10.0,
+            },
+        )];
+
+        assert_eq!(topmost_hit(&hitboxes, (100.0, 100.0)), None);
+    }
+}
+
+/// Builds this frame's draw-command buffer from the current geometry,
+/// highlighting whichever entity is hovered or has keyboard focus.
+pub struct DrawCmdSystem;
+
+impl<'a> System<'a> for DrawCmdSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Renderable>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Color>,
+        Read<'a, Hovered>,
+        Read<'a, KeyboardFocus>,
+        Write<'a, DrawQueue>,
+    );
+
+    fn run(&mut self, (entities, rends, positions, cols, hovered, kb_focus, mut queue): Self::SystemData) {
+        queue.0.clear();
+        queue.0.push(DrawCmd::Clear);
+        for (entity, _rend, pos, col) in (&entities, &rends, &positions, &cols).join() {
+            let highlighted = hovered.0 == Some(entity) || kb_focus.0 == Some(entity);
+            let color = if highlighted {
+                Color { b: 0, ..*col }
+            } else {
+                *col
+            };
+            queue.0.push(DrawCmd::Circle {
+                x: pos.x,
+                y: pos.y,
+                r: RAD as f32,
+                color,
+            });
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use petgraph::prelude::*;
+use petgraph::visit::EdgeRef;
+
+/// Produces a 2D position for every node in a graph, laid out for a drawing
+/// area of size `bounds`. Whether positions are clamped to `bounds` is up to
+/// each implementation.
+/// Generic over the node weight `N`: placement only depends on graph
+/// structure (node count, edges, edge weights), never on node data.
+pub trait Layout<N> {
+    fn position(&self, graph: &DiGraph<N, f32>, bounds: (f32, f32)) -> HashMap<NodeIndex, (f32, f32)>;
+}
+
+/// Fixed row/column placement; ignores edges and wraps left-to-right.
+/// Only `bounds.0` is used, to pick the row length; positions are not
+/// clamped, so rows beyond the height of `bounds` fall outside it.
+pub struct GridLayout {
+    pub cell: f32,
+}
+
+impl<N> Layout<N> for GridLayout {
+    fn position(&self, graph: &DiGraph<N, f32>, bounds: (f32, f32)) -> HashMap<NodeIndex, (f32, f32)> {
+        let row_count = ((bounds.0 / (self.cell * 2.0)).floor().max(1.0)) as usize;
+        graph
+            .node_indices()
+            .enumerate()
+            .map(|(i, idx)| {
+                let x = self.cell + (i % row_count) as f32 * (self.cell * 2.0);
+                let y = self.cell + (i / row_count) as f32 * (self.cell * 2.0);
+                (idx, (x, y))
+            })
+            .collect()
+    }
+}
+
+/// Charged-particle repulsion plus damped springs along edges, so connected
+/// nodes cluster instead of overlapping. Positions are clamped to `bounds`.
+pub struct ForceDirectedLayout {
+    pub iterations: usize,
+    /// Repulsion constant `k_r` in the `k_r / d^2` term.
+    pub repulsion: f32,
+    pub rest_len: f32,
+    /// Per-step velocity damping, in `0.0..1.0`.
+    pub damping: f32,
+    pub dt: f32,
+}
+
+impl Default for ForceDirectedLayout {
+    fn default() -> Self {
+        ForceDirectedLayout {
+            iterations: 200,
+            repulsion: 20_000.0,
+            rest_len: 120.0,
+            damping: 0.85,
+            dt: 0.1,
+        }
+    }
+}
+
+impl<N> Layout<N> for ForceDirectedLayout {
+    fn position(&self, graph: &DiGraph<N, f32>, bounds: (f32, f32)) -> HashMap<NodeIndex, (f32, f32)> {
+        let indices: Vec<NodeIndex> = graph.node_indices().collect();
+        let radius = bounds.0.min(bounds.1) * 0.25;
+        let mut pos: HashMap<NodeIndex, (f32, f32)> = indices
+            .iter()
+            .enumerate()
+            .map(|(i, &idx)| {
+                let angle = i as f32 / indices.len().max(1) as f32 * std::f32::consts::TAU;
+                (
+                    idx,
+                    (
+                        bounds.0 / 2.0 + angle.cos() * radius,
+                        bounds.1 / 2.0 + angle.sin() * radius,
+                    ),
+                )
+            })
+            .collect();
+        let mut vel: HashMap<NodeIndex, (f32, f32)> =
+            indices.iter().map(|&idx| (idx, (0.0, 0.0))).collect();
+
+        for _ in 0..self.iterations {
+            let mut force: HashMap<NodeIndex, (f32, f32)> =
+                indices.iter().map(|&idx| (idx, (0.0, 0.0))).collect();
+
+            for (i, &a) in indices.iter().enumerate() {
+                for &b in &indices[i + 1..] {
+                    let (ax, ay) = pos[&a];
+                    let (bx, by) = pos[&b];
+                    let dx = ax - bx;
+                    let dy = ay - by;
+                    let dist_sq = (dx * dx + dy * dy).max(1.0);
+                    let dist = dist_sq.sqrt();
+                    let f = self.repulsion / dist_sq;
+                    let (fx, fy) = (dx / dist * f, dy / dist * f);
+                    let fa = force.get_mut(&a).expect("node has a force entry");
+                    fa.0 += fx;
+                    fa.1 += fy;
+                    let fb = force.get_mut(&b).expect("node has a force entry");
+                    fb.0 -= fx;
+                    fb.1 -= fy;
+                }
+            }
+
+            for edge in graph.edge_references() {
+                let a = edge.source();
+                let b = edge.target();
+                let (ax, ay) = pos[&a];
+                let (bx, by) = pos[&b];
+                let dx = bx - ax;
+                let dy = by - ay;
+                let dist = (dx * dx + dy * dy).sqrt().max(1.0);
+                let f = (dist - self.rest_len) * edge.weight().max(0.1);
+                let (fx, fy) = (dx / dist * f, dy / dist * f);
+                let fa = force.get_mut(&a).expect("node has a force entry");
+                fa.0 += fx;
+                fa.1 += fy;
+                let fb = force.get_mut(&b).expect("node has a force entry");
+                fb.0 -= fx;
+                fb.1 -= fy;
+            }
+
+            for &idx in &indices {
+                let f = force[&idx];
+                let v = vel.get_mut(&idx).expect("node has a velocity entry");
+                v.0 = (v.0 + f.0 * self.dt) * self.damping;
+                v.1 = (v.1 + f.1 * self.dt) * self.damping;
+                let p = pos.get_mut(&idx).expect("node has a position entry");
+                p.0 = (p.0 + v.0 * self.dt).clamp(0.0, bounds.0);
+                p.1 = (p.1 + v.1 * self.dt).clamp(0.0, bounds.1);
+            }
+        }
+
+        pos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_graph(n: usize) -> DiGraph<(), f32> {
+        let mut graph = DiGraph::new();
+        let nodes: Vec<NodeIndex> = (0..n).map(|_| graph.add_node(())).collect();
+        for pair in nodes.windows(2) {
+            graph.add_edge(pair[0], pair[1], 1.0);
+        }
+        graph
+    }
+
+    #[test]
+    fn grid_layout_stays_within_bounds() {
+        let graph = test_graph(8);
+        let bounds = (300.0, 300.0);
+        let positions = GridLayout { cell: 50.0 }.position(&graph, bounds);
+        for (x, y) in positions.values() {
+            assert!((0.0..=bounds.0).contains(x));
+            assert!((0.0..=bounds.1).contains(y));
+        }
+    }
+
+    #[test]
+    fn force_directed_layout_clamps_to_bounds() {
+        let graph = test_graph(8);
+        let bounds = (400.0, 300.0);
+        let positions = ForceDirectedLayout::default().position(&graph, bounds);
+        for (x, y) in positions.values() {
+            assert!((0.0..=bounds.0).contains(x));
+            assert!((0.0..=bounds.1).contains(y));
+        }
+    }
+}